@@ -2,12 +2,15 @@ use crate::github::graphql::get_existing_pull_request::PullRequest;
 use crate::github::graphql::get_pull_request_from_branch::PullRequestState;
 use crate::types::package_identifier::PackageIdentifier;
 use crate::types::package_version::PackageVersion;
+use color_eyre::eyre::bail;
 use color_eyre::Result;
 use crossterm::style::Stylize;
 use futures_util::{stream, StreamExt, TryStreamExt};
 use inquire::Confirm;
+use similar::{ChangeTag, TextDiff};
 use std::env;
-use std::path::Path;
+use std::fmt::Write as _;
+use std::path::{Component, Path};
 use std::str::FromStr;
 use tokio::fs;
 use tokio::fs::File;
@@ -29,25 +32,311 @@ pub fn prompt_existing_pull_request(
         pull_request.created_at.time()
     );
     println!("{}", pull_request.url.as_str().blue());
-    let proceed = if env::var("CI").is_ok_and(|ci| bool::from_str(&ci) == Ok(true)) {
-        false
-    } else {
-        Confirm::new("Would you like to proceed?").prompt()?
-    };
-    Ok(proceed)
+
+    if is_ci() {
+        return Ok(false);
+    }
+
+    if can_launch_browser()
+        && Confirm::new("Would you like to open it in your browser?")
+            .with_default(false)
+            .prompt()?
+    {
+        if let Err(error) = open::that(pull_request.url.as_str()) {
+            eprintln!(
+                "Failed to open {} in the browser: {error}",
+                pull_request.url.as_str()
+            );
+        }
+    }
+
+    Ok(Confirm::new("Would you like to proceed?").prompt()?)
+}
+
+/// Whether we're running in CI, where prompts would hang and should be
+/// skipped outright.
+fn is_ci() -> bool {
+    env::var("CI").is_ok_and(|ci| bool::from_str(&ci) == Ok(true))
+}
+
+/// Whether opening a URL in a browser is likely to work at all: not inside a
+/// Docker container, not WSL, and (on Linux) not missing both `DISPLAY` and
+/// `BROWSER`, the same signals `is-docker`/`is-wsl` use. This only gates the
+/// browser-open offer, not whether we can still prompt the user — plenty of
+/// interactive sessions (WSL, SSH without X11 forwarding) have no usable
+/// browser but are otherwise perfectly able to answer a `Confirm` prompt.
+fn can_launch_browser() -> bool {
+    !is_docker() && !is_wsl() && !is_missing_display()
+}
+
+fn is_docker() -> bool {
+    Path::new("/.dockerenv").exists()
+        || std::fs::read_to_string("/proc/1/cgroup")
+            .is_ok_and(|cgroup| cgroup.contains("docker") || cgroup.contains("containerd"))
+}
+
+fn is_wsl() -> bool {
+    std::fs::read_to_string("/proc/sys/kernel/osrelease")
+        .is_ok_and(|release| release.to_lowercase().contains("microsoft"))
+}
+
+#[cfg(target_os = "linux")]
+fn is_missing_display() -> bool {
+    env::var_os("DISPLAY").is_none() && env::var_os("BROWSER").is_none()
 }
 
-pub async fn write_changes_to_dir(changes: &[(String, String)], output: &Path) -> Result<()> {
-    fs::create_dir_all(output).await?;
+#[cfg(not(target_os = "linux"))]
+fn is_missing_display() -> bool {
+    false
+}
+
+/// How a single manifest path would be (or was) affected by a write.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Unchanged,
+}
+
+/// Writes `changes` into `output`, returning how each path was affected.
+///
+/// When `dry_run` is `true`, no files are written or directories created;
+/// instead, any pre-existing file at each target path is diffed line-by-line
+/// against the generated content and a coloured unified diff is printed, so
+/// contributors can review exactly what Komac would emit before it touches
+/// disk.
+pub async fn write_changes_to_dir(
+    changes: &[(String, String)],
+    output: &Path,
+    dry_run: bool,
+) -> Result<Vec<(String, ChangeKind)>> {
+    if !dry_run {
+        fs::create_dir_all(output).await?;
+    }
     stream::iter(changes.iter())
         .map(|(path, content)| async move {
-            if let Some(file_name) = Path::new(path).file_name() {
-                let mut file = File::create(output.join(file_name)).await?;
+            let relative_path = sanitise_relative_path(path)?;
+            let full_path = output.join(relative_path);
+
+            let existing = fs::read_to_string(&full_path).await.ok();
+            let kind = match &existing {
+                None => ChangeKind::Added,
+                Some(previous) if previous == content => ChangeKind::Unchanged,
+                Some(_) => ChangeKind::Modified,
+            };
+
+            if dry_run {
+                print!(
+                    "{}",
+                    render_diff(path, existing.as_deref().unwrap_or_default(), content, kind)
+                );
+            } else {
+                if let Some(parent) = full_path.parent() {
+                    fs::create_dir_all(parent).await?;
+                }
+                let mut file = File::create(full_path).await?;
                 file.write_all(content.as_bytes()).await?;
             }
-            Ok::<(), color_eyre::eyre::Error>(())
+
+            Ok::<_, color_eyre::eyre::Error>((path.clone(), kind))
         })
         .buffer_unordered(2)
         .try_collect()
         .await
-}
\ No newline at end of file
+}
+
+/// Renders a one-line added/modified/unchanged summary for `path`, followed
+/// by a coloured unified diff between `previous` and `next` when it isn't
+/// unchanged, as a single `String`.
+///
+/// Building the whole thing up-front and emitting it with one `print!` call
+/// (rather than printing line-by-line) keeps each path's output from
+/// interleaving with another path's when diffs are rendered concurrently.
+fn render_diff(path: &str, previous: &str, next: &str, kind: ChangeKind) -> String {
+    let label = match kind {
+        ChangeKind::Added => "added".green(),
+        ChangeKind::Modified => "modified".yellow(),
+        ChangeKind::Unchanged => "unchanged".grey(),
+    };
+    let mut output = format!("{path} ({label})\n");
+
+    if kind == ChangeKind::Unchanged {
+        return output;
+    }
+
+    let diff = TextDiff::from_lines(previous, next);
+    for change in diff.iter_all_changes() {
+        let line = change.to_string_lossy();
+        let _ = match change.tag() {
+            ChangeTag::Delete => write!(output, "{}{line}", "-".red()),
+            ChangeTag::Insert => write!(output, "{}{line}", "+".green()),
+            ChangeTag::Equal => write!(output, " {line}"),
+        };
+    }
+    output
+}
+
+/// Ensures `path` is a relative path that stays within the output directory,
+/// rejecting absolute paths and any `..` components that would allow it to
+/// escape the output root.
+fn sanitise_relative_path(path: &str) -> Result<&Path> {
+    let path = Path::new(path);
+    if path.is_absolute()
+        || path.components().any(|component| {
+            matches!(
+                component,
+                Component::Prefix(_) | Component::RootDir | Component::ParentDir
+            )
+        })
+    {
+        bail!("{} is not a valid relative manifest path", path.display());
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sanitise_relative_path;
+
+    #[test]
+    fn accepts_nested_relative_paths() {
+        let path = "manifests/m/Microsoft/PowerToys/0.75.1/Microsoft.PowerToys.yaml";
+        assert_eq!(
+            sanitise_relative_path(path).unwrap(),
+            std::path::Path::new(path)
+        );
+    }
+
+    #[test]
+    fn rejects_absolute_paths() {
+        assert!(sanitise_relative_path("/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn rejects_parent_dir_traversal() {
+        assert!(sanitise_relative_path("../../etc/passwd").is_err());
+        assert!(sanitise_relative_path("manifests/../../../etc/passwd").is_err());
+    }
+}
+
+/// Options for committing the written manifests into a local git repository
+/// rooted at the output directory; the files are still written there as well.
+pub struct CommitOptions<'a> {
+    pub identifier: &'a PackageIdentifier,
+    pub version: &'a PackageVersion,
+}
+
+/// The result of [`write_changes_and_commit`] committing its output, surfaced so
+/// the caller can print or push the branch that now holds the manifests.
+pub struct CommitOutcome {
+    pub branch_name: String,
+    pub commit_id: gix::ObjectId,
+}
+
+/// Writes `changes` into `output` and commits them to a git repository rooted
+/// there, initialising the repository first if one doesn't already exist.
+///
+/// This mirrors the bare-repo/worktree setup `gix::create` performs for
+/// `gix clone`, so the commit is produced without shelling out to `git`.
+pub async fn write_changes_and_commit(
+    changes: &[(String, String)],
+    output: &Path,
+    options: CommitOptions<'_>,
+) -> Result<Option<CommitOutcome>> {
+    let written = write_changes_to_dir(changes, output, false).await?;
+    if written
+        .iter()
+        .all(|(_, kind)| *kind == ChangeKind::Unchanged)
+    {
+        return Ok(None);
+    }
+
+    let paths = changes
+        .iter()
+        .map(|(path, _)| sanitise_relative_path(path).map(Path::to_path_buf))
+        .collect::<Result<Vec<_>>>()?;
+    let output = output.to_path_buf();
+    let message = format!(
+        "New version: {} version {}",
+        options.identifier, options.version
+    );
+
+    tokio::task::spawn_blocking(move || commit_paths(&output, &paths, &message))
+        .await?
+        .map(Some)
+}
+
+/// Stages `paths` relative to `repo_dir` and creates a commit with `message`,
+/// initialising the repository in `repo_dir` if it doesn't already exist.
+fn commit_paths(
+    repo_dir: &Path,
+    paths: &[std::path::PathBuf],
+    message: &str,
+) -> Result<CommitOutcome> {
+    if gix::open(repo_dir).is_err() {
+        // `create::into` only lays out the `.git` directory/worktree; it
+        // doesn't load config the way `open` does, so re-open afterwards to
+        // get a fully-configured `Repository`.
+        gix::create::into(
+            repo_dir,
+            gix::create::Kind::WithWorktree,
+            gix::create::Options::default(),
+        )?;
+    }
+    let repo = gix::open(repo_dir)?;
+
+    // Start from whatever HEAD already points at so re-running this against
+    // an existing repo builds on top of earlier commits instead of dropping
+    // paths that aren't part of this run's `changes`.
+    let base_tree = repo
+        .head_id()
+        .ok()
+        .and_then(|id| id.object().ok())
+        .and_then(|commit| commit.peel_to_tree().ok())
+        .map_or_else(|| repo.empty_tree().id, |tree| tree.id);
+
+    let mut editor = repo.edit_tree(base_tree)?;
+    for path in paths {
+        let content = std::fs::read(repo_dir.join(path))?;
+        let blob_id = repo.write_blob(content)?;
+        let components = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned());
+        editor.upsert(components, gix::object::tree::EntryKind::Blob, blob_id)?;
+    }
+    let tree_id = editor.write()?.detach();
+
+    // Freshly-initialised repos (and minimal/CI environments) may have no
+    // `user.name`/`user.email` configured, so commit with an explicit
+    // identity rather than relying on `repo.commit`'s config lookup.
+    let signature = commit_signature();
+    let commit_id = repo
+        .commit_as(
+            &signature,
+            &signature,
+            "HEAD",
+            message,
+            tree_id,
+            repo.head_id().ok(),
+        )?
+        .detach();
+
+    let branch_name = repo
+        .head_name()?
+        .map_or_else(|| "main".to_string(), |name| name.shorten().to_string());
+
+    Ok(CommitOutcome {
+        branch_name,
+        commit_id,
+    })
+}
+
+/// The committer/author identity used for manifest commits, so this works
+/// without relying on a global `user.name`/`user.email` being configured.
+fn commit_signature() -> gix::actor::Signature {
+    gix::actor::Signature {
+        name: "Komac".into(),
+        email: "komac@users.noreply.github.com".into(),
+        time: gix::date::Time::now_local_or_utc(),
+    }
+}